@@ -0,0 +1,198 @@
+//! Async counterpart to the blocking [`crate::DhtSensor`] path.
+
+use embedded_hal::digital::{InputPin, OutputPin, PinState};
+use embedded_hal_async::delay::DelayNs;
+
+use crate::{DhtError, InterruptControl, Reading};
+
+/// A trait for asynchronously reading data from the sensor
+///
+/// This mirrors [`crate::DhtSensor`], but awaits the wake-up delay and the
+/// per-bit level sampling instead of busy-polling.
+#[allow(async_fn_in_trait)]
+// `Send` on the returned future doesn't matter here: embedded executors (Embassy and similar)
+// run tasks single-threaded, so there's no cross-thread handoff to worry about.
+pub trait DhtSensorAsync<HE> {
+    /// Reads data from the sensor and returns a `Reading`
+    async fn read(&mut self) -> Result<Reading, DhtError<HE>>;
+}
+
+#[doc(hidden)]
+pub struct DhtAsync<
+    HE,
+    ID: InterruptControl,
+    D: DelayNs,
+    P: InputPin<Error = HE> + OutputPin<Error = HE>,
+> {
+    interrupt_disabler: ID,
+    delay: D,
+    pin: P,
+}
+
+impl<HE, ID: InterruptControl, D: DelayNs, P: InputPin<Error = HE> + OutputPin<Error = HE>>
+    DhtAsync<HE, ID, D, P>
+{
+    fn new(interrupt_disabler: ID, delay: D, pin: P) -> Self {
+        Self {
+            interrupt_disabler,
+            delay,
+            pin,
+        }
+    }
+
+    async fn read(
+        &mut self,
+        parse_data: fn(&[u8]) -> (f32, f32),
+    ) -> Result<Reading, DhtError<HE>> {
+        self.interrupt_disabler.disable_interrupts();
+        let res = self.read_uninterruptible(parse_data).await;
+        self.interrupt_disabler.enable_interrupts();
+        res
+    }
+
+    async fn read_uninterruptible(
+        &mut self,
+        parse_data: fn(&[u8]) -> (f32, f32),
+    ) -> Result<Reading, DhtError<HE>> {
+        let mut buf: [u8; 5] = [0; 5];
+
+        // Wake up the sensor
+        self.pin.set_low()?;
+        self.delay.delay_us(3000).await;
+
+        // Ask for data
+        self.pin.set_high()?;
+        self.delay.delay_us(25).await;
+
+        // Wait for DHT to signal data is ready (~80us low followed by ~80us high)
+        self.wait_for_level(PinState::High, 85, DhtError::NotPresent)
+            .await?;
+        self.wait_for_level(PinState::Low, 85, DhtError::NotPresent)
+            .await?;
+
+        // Now read 40 data bits
+        for bit in 0..40 {
+            // Wait ~50us for high
+            self.wait_for_level(PinState::High, 55, DhtError::Timeout)
+                .await?;
+
+            // See how long it takes to go low, with max of 70us
+            let elapsed = self
+                .wait_for_level(PinState::Low, 70, DhtError::Timeout)
+                .await?;
+            // If it took more than 30 counted iterations to go low, it's a '1' bit. This mirrors
+            // `CountingPulseTiming::is_one_bit` in the blocking `Dht` implementation, since this
+            // path measures elapsed time the same way: counting `delay_us(1)` iterations rather
+            // than reading a clock. Keep the two in sync.
+            if elapsed > 30 {
+                let byte = bit / 8;
+                let shift = 7 - bit % 8;
+                buf[byte] |= 1 << shift;
+            }
+        }
+
+        let checksum = (buf[0..=3]
+            .iter()
+            .fold(0u16, |accum, next| accum + *next as u16)
+            & 0xff) as u8;
+        if buf[4] == checksum {
+            let (humidity, temperature) = parse_data(&buf);
+            if !(0.0..=100.0).contains(&humidity) {
+                Err(DhtError::InvalidData)
+            } else {
+                Ok(Reading::new(humidity, temperature, buf))
+            }
+        } else {
+            Err(DhtError::ChecksumMismatch(buf[4], checksum))
+        }
+    }
+
+    async fn wait_for_level(
+        &mut self,
+        level: PinState,
+        timeout_us: u32,
+        on_timeout: DhtError<HE>,
+    ) -> Result<u32, DhtError<HE>> {
+        for elapsed in 0..=timeout_us {
+            let is_ready = match level {
+                PinState::High => self.pin.is_high(),
+                PinState::Low => self.pin.is_low(),
+            }?;
+
+            if is_ready {
+                return Ok(elapsed);
+            }
+            self.delay.delay_us(1).await;
+        }
+        Err(on_timeout)
+    }
+}
+
+/// An async DHT11 sensor
+pub struct Dht11Async<
+    HE,
+    ID: InterruptControl,
+    D: DelayNs,
+    P: InputPin<Error = HE> + OutputPin<Error = HE>,
+> {
+    dht: DhtAsync<HE, ID, D, P>,
+}
+
+impl<HE, ID: InterruptControl, D: DelayNs, P: InputPin<Error = HE> + OutputPin<Error = HE>>
+    Dht11Async<HE, ID, D, P>
+{
+    pub fn new(interrupt_disabler: ID, delay: D, pin: P) -> Self {
+        Self {
+            dht: DhtAsync::new(interrupt_disabler, delay, pin),
+        }
+    }
+
+    fn parse_data(buf: &[u8]) -> (f32, f32) {
+        (buf[0] as f32, buf[2] as f32)
+    }
+}
+
+impl<HE, ID: InterruptControl, D: DelayNs, P: InputPin<Error = HE> + OutputPin<Error = HE>>
+    DhtSensorAsync<HE> for Dht11Async<HE, ID, D, P>
+{
+    async fn read(&mut self) -> Result<Reading, DhtError<HE>> {
+        self.dht.read(Dht11Async::<HE, ID, D, P>::parse_data).await
+    }
+}
+
+/// An async DHT22 sensor
+pub struct Dht22Async<
+    HE,
+    ID: InterruptControl,
+    D: DelayNs,
+    P: InputPin<Error = HE> + OutputPin<Error = HE>,
+> {
+    dht: DhtAsync<HE, ID, D, P>,
+}
+
+impl<HE, ID: InterruptControl, D: DelayNs, P: InputPin<Error = HE> + OutputPin<Error = HE>>
+    Dht22Async<HE, ID, D, P>
+{
+    pub fn new(interrupt_disabler: ID, delay: D, pin: P) -> Self {
+        Self {
+            dht: DhtAsync::new(interrupt_disabler, delay, pin),
+        }
+    }
+
+    fn parse_data(buf: &[u8]) -> (f32, f32) {
+        let humidity = (((buf[0] as u16) << 8) | buf[1] as u16) as f32 / 10.0;
+        let mut temperature = ((((buf[2] & 0x7f) as u16) << 8) | buf[3] as u16) as f32 / 10.0;
+        if buf[2] & 0x80 != 0 {
+            temperature = -temperature;
+        }
+        (humidity, temperature)
+    }
+}
+
+impl<HE, ID: InterruptControl, D: DelayNs, P: InputPin<Error = HE> + OutputPin<Error = HE>>
+    DhtSensorAsync<HE> for Dht22Async<HE, ID, D, P>
+{
+    async fn read(&mut self) -> Result<Reading, DhtError<HE>> {
+        self.dht.read(Dht22Async::<HE, ID, D, P>::parse_data).await
+    }
+}
@@ -2,19 +2,34 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use core::fmt;
+use core::marker::PhantomData;
 use embedded_hal::{
     delay::DelayNs,
     digital::{InputPin, OutputPin, PinState},
 };
 
+#[cfg(feature = "async")]
+mod async_dht;
+#[cfg(feature = "async")]
+pub use async_dht::*;
+
 /// A sensor reading
 #[derive(Debug, Clone, Copy)]
 pub struct Reading {
     humidity: f32,
     temperature: f32,
+    raw: [u8; 5],
 }
 
 impl Reading {
+    pub(crate) fn new(humidity: f32, temperature: f32, raw: [u8; 5]) -> Self {
+        Self {
+            humidity,
+            temperature,
+            raw,
+        }
+    }
+
     /// Returns the ambient humidity, as a percentage value from 0.0 to 100.0
     pub fn humidity(&self) -> f32 {
         self.humidity
@@ -24,6 +39,60 @@ impl Reading {
     pub fn temperature(&self) -> f32 {
         self.temperature
     }
+
+    /// Returns the raw, checksum-validated 40-bit payload read from the sensor, before scaling
+    /// into [`humidity`](Self::humidity) and [`temperature`](Self::temperature)
+    ///
+    /// This is useful for callers that need to do their own unit handling, detect sensor-variant
+    /// scaling differences, or debug marginal sensors.
+    pub fn raw(&self) -> [u8; 5] {
+        self.raw
+    }
+
+    /// Returns the ambient temperature, in degrees Fahrenheit
+    pub fn temperature_fahrenheit(&self) -> f32 {
+        self.temperature * 9.0 / 5.0 + 32.0
+    }
+
+    /// Returns the dew point, in degrees Celsius, computed via the Magnus-Tetens approximation
+    pub fn dew_point(&self) -> f32 {
+        const A: f32 = 17.27;
+        const B: f32 = 237.7;
+        let gamma = ln(self.humidity / 100.0) + (A * self.temperature) / (B + self.temperature);
+        (B * gamma) / (A - gamma)
+    }
+
+    /// Returns the heat index ("feels like" temperature), in degrees Fahrenheit, computed via the
+    /// NOAA Rothfusz regression (falling back to the simpler Steadman approximation below 80°F,
+    /// where the regression is not considered valid)
+    pub fn heat_index(&self) -> f32 {
+        let t = self.temperature_fahrenheit();
+        let r = self.humidity;
+        if t < 80.0 {
+            0.5 * (t + 61.0 + (t - 68.0) * 1.2 + r * 0.094)
+        } else {
+            // Computed in f64: the regression's coefficients need more precision than f32 can
+            // represent exactly.
+            let (t, r) = (t as f64, r as f64);
+            (-42.379 + 2.04901523 * t + 10.14333127 * r
+                - 0.22475541 * t * r
+                - 0.00683783 * t * t
+                - 0.05481717 * r * r
+                + 0.00122874 * t * t * r
+                + 0.00085282 * t * r * r
+                - 0.00000199 * t * t * r * r) as f32
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+fn ln(x: f32) -> f32 {
+    x.ln()
+}
+
+#[cfg(not(feature = "std"))]
+fn ln(x: f32) -> f32 {
+    libm::logf(x)
 }
 
 /// A type detailing various errors the DHT sensor can return
@@ -90,25 +159,133 @@ pub trait DhtSensor<HE> {
     fn read(&mut self) -> Result<Reading, DhtError<HE>>;
 }
 
+/// A monotonic, free-running microsecond clock, used to time pulse widths accurately across a
+/// wide range of clock speeds
+pub trait MonotonicClock {
+    /// Returns a monotonically increasing timestamp, in microseconds
+    fn now_us(&mut self) -> u32;
+}
+
+/// Strategy used by [`Dht`] to measure how long a GPIO pin takes to reach a given level when
+/// decoding pulse widths
+///
+/// Implemented once for plain iteration counting ([`CountingPulseTiming`], the default, portable
+/// but speed-dependent) and once generically for any [`MonotonicClock`]. Each implementation also
+/// decides the bit-decode cutoff for its own `elapsed` scale via [`is_one_bit`](Self::is_one_bit),
+/// since the two strategies' `elapsed` values aren't necessarily comparable.
+pub trait PulseTiming<HE, D: DelayNs, P: InputPin<Error = HE>> {
+    /// Waits up to `timeout_us` for `pin` to reach `level`, returning the elapsed time on success
+    /// (in this implementation's own units, which need not be microseconds), or `None` on timeout
+    fn wait_for_level(
+        &mut self,
+        pin: &mut P,
+        delay: &mut D,
+        level: PinState,
+        timeout_us: u32,
+    ) -> Result<Option<u32>, HE>;
+
+    /// Given the `elapsed` time (as returned by [`wait_for_level`](Self::wait_for_level)) a data
+    /// bit's high pulse took, returns whether it should be decoded as a '1' bit
+    fn is_one_bit(elapsed: u32) -> bool;
+}
+
+/// The default [`PulseTiming`] strategy: counts `delay_us(1)` iterations rather than reading a
+/// clock, so it works with any [`DelayNs`] implementation but is sensitive to per-iteration
+/// overhead
+#[doc(hidden)]
+pub struct CountingPulseTiming;
+
+impl<HE, D: DelayNs, P: InputPin<Error = HE>> PulseTiming<HE, D, P> for CountingPulseTiming {
+    fn wait_for_level(
+        &mut self,
+        pin: &mut P,
+        delay: &mut D,
+        level: PinState,
+        timeout_us: u32,
+    ) -> Result<Option<u32>, HE> {
+        for elapsed in 0..=timeout_us {
+            let is_ready = match level {
+                PinState::High => pin.is_high(),
+                PinState::Low => pin.is_low(),
+            }?;
+
+            if is_ready {
+                return Ok(Some(elapsed));
+            }
+            delay.delay_us(1);
+        }
+        Ok(None)
+    }
+
+    fn is_one_bit(elapsed: u32) -> bool {
+        // If it took more than 30 counted iterations to go low, it's a '1' bit. This is the
+        // original, field-tested cutoff for counted iterations specifically; it's kept as-is
+        // (rather than unified with `MonotonicClock`'s microsecond-based cutoff below) since an
+        // iteration isn't necessarily a microsecond and changing it isn't safe without
+        // re-validating against real sensors.
+        elapsed > 30
+    }
+}
+
+impl<HE, D: DelayNs, P: InputPin<Error = HE>, C: MonotonicClock> PulseTiming<HE, D, P> for C {
+    fn wait_for_level(
+        &mut self,
+        pin: &mut P,
+        _delay: &mut D,
+        level: PinState,
+        timeout_us: u32,
+    ) -> Result<Option<u32>, HE> {
+        let start = self.now_us();
+        loop {
+            let is_ready = match level {
+                PinState::High => pin.is_high(),
+                PinState::Low => pin.is_low(),
+            }?;
+            let elapsed = self.now_us().wrapping_sub(start);
+
+            if is_ready {
+                return Ok(Some(elapsed));
+            }
+            if elapsed >= timeout_us {
+                return Ok(None);
+            }
+        }
+    }
+
+    fn is_one_bit(elapsed: u32) -> bool {
+        // A '0' bit is a ~26us high pulse and a '1' bit is a ~70us one, so threshold at 40us,
+        // between the two.
+        elapsed >= 40
+    }
+}
+
 #[doc(hidden)]
 pub struct Dht<
     HE,
     ID: InterruptControl,
     D: DelayNs,
+    W: PulseTiming<HE, D, P>,
     P: InputPin<Error = HE> + OutputPin<Error = HE>,
 > {
     interrupt_disabler: ID,
     delay: D,
+    timing: W,
     pin: P,
 }
 
-impl<HE, ID: InterruptControl, D: DelayNs, P: InputPin<Error = HE> + OutputPin<Error = HE>>
-    Dht<HE, ID, D, P>
+impl<
+        HE,
+        ID: InterruptControl,
+        D: DelayNs,
+        W: PulseTiming<HE, D, P>,
+        P: InputPin<Error = HE> + OutputPin<Error = HE>,
+    > Dht<HE, ID, D, W, P>
 {
-    fn new(interrupt_disabler: ID, delay: D, pin: P) -> Self {
+    fn new(interrupt_disabler: ID, delay: D, timing: W, pin: P) -> Self {
         Self {
             interrupt_disabler,
             delay,
+            timing,
             pin,
         }
     }
@@ -145,8 +322,10 @@ impl<HE, ID: InterruptControl, D: DelayNs, P: InputPin<Error = HE> + OutputPin<E
 
             // See how long it takes to go low, with max of 70us
             let elapsed = self.wait_for_level(PinState::Low, 70, DhtError::Timeout)?;
-            // If it took at least 30us to go low, it's a '1' bit
-            if elapsed > 30 {
+            // Each `PulseTiming` implementation decides its own bit-decode cutoff, since
+            // `CountingPulseTiming`'s counted iterations and a `MonotonicClock`'s microseconds
+            // aren't necessarily the same scale.
+            if W::is_one_bit(elapsed) {
                 let byte = bit / 8;
                 let shift = 7 - bit % 8;
                 buf[byte] |= 1 << shift;
@@ -162,10 +341,7 @@ impl<HE, ID: InterruptControl, D: DelayNs, P: InputPin<Error = HE> + OutputPin<E
             if !(0.0..=100.0).contains(&humidity) {
                 Err(DhtError::InvalidData)
             } else {
-                Ok(Reading {
-                    humidity,
-                    temperature,
-                })
+                Ok(Reading::new(humidity, temperature, buf))
             }
         } else {
             Err(DhtError::ChecksumMismatch(buf[4], checksum))
@@ -178,18 +354,9 @@ impl<HE, ID: InterruptControl, D: DelayNs, P: InputPin<Error = HE> + OutputPin<E
         timeout_us: u32,
         on_timeout: DhtError<HE>,
     ) -> Result<u32, DhtError<HE>> {
-        for elapsed in 0..=timeout_us {
-            let is_ready = match level {
-                PinState::High => self.pin.is_high(),
-                PinState::Low => self.pin.is_low(),
-            }?;
-
-            if is_ready {
-                return Ok(elapsed);
-            }
-            self.delay.delay_us(1);
-        }
-        Err(on_timeout)
+        self.timing
+            .wait_for_level(&mut self.pin, &mut self.delay, level, timeout_us)?
+            .ok_or(on_timeout)
     }
 }
 
@@ -200,7 +367,7 @@ pub struct Dht11<
     D: DelayNs,
     P: InputPin<Error = HE> + OutputPin<Error = HE>,
 > {
-    dht: Dht<HE, ID, D, P>,
+    dht: Dht<HE, ID, D, CountingPulseTiming, P>,
 }
 
 impl<HE, ID: InterruptControl, D: DelayNs, P: InputPin<Error = HE> + OutputPin<Error = HE>>
@@ -208,7 +375,7 @@ impl<HE, ID: InterruptControl, D: DelayNs, P: InputPin<Error = HE> + OutputPin<E
 {
     pub fn new(interrupt_disabler: ID, delay: D, pin: P) -> Self {
         Self {
-            dht: Dht::new(interrupt_disabler, delay, pin),
+            dht: Dht::new(interrupt_disabler, delay, CountingPulseTiming, pin),
         }
     }
 
@@ -225,6 +392,47 @@ impl<HE, ID: InterruptControl, D: DelayNs, P: InputPin<Error = HE> + OutputPin<E
     }
 }
 
+/// A DHT11 sensor that decodes the decimal humidity/temperature bytes and the temperature sign
+/// bit, for newer DHT11 units with sub-one-degree resolution
+///
+/// Older DHT11 modules leave these bytes at zero, so [`Dht11`] remains the safe default; use this
+/// type only if you know your sensor reports decimal precision.
+pub struct Dht11FullResolution<
+    HE,
+    ID: InterruptControl,
+    D: DelayNs,
+    P: InputPin<Error = HE> + OutputPin<Error = HE>,
+> {
+    dht: Dht<HE, ID, D, CountingPulseTiming, P>,
+}
+
+impl<HE, ID: InterruptControl, D: DelayNs, P: InputPin<Error = HE> + OutputPin<Error = HE>>
+    Dht11FullResolution<HE, ID, D, P>
+{
+    pub fn new(interrupt_disabler: ID, delay: D, pin: P) -> Self {
+        Self {
+            dht: Dht::new(interrupt_disabler, delay, CountingPulseTiming, pin),
+        }
+    }
+}
+
+impl<HE, ID: InterruptControl, D: DelayNs, P: InputPin<Error = HE> + OutputPin<Error = HE>>
+    DhtSensor<HE> for Dht11FullResolution<HE, ID, D, P>
+{
+    fn read(&mut self) -> Result<Reading, DhtError<HE>> {
+        self.dht.read(dht11_full_resolution_parse_data)
+    }
+}
+
+fn dht11_full_resolution_parse_data(buf: &[u8]) -> (f32, f32) {
+    let humidity = buf[0] as f32 + buf[1] as f32 * 0.1;
+    let mut temperature = buf[2] as f32 + (buf[3] & 0x0f) as f32 * 0.1;
+    if buf[3] & 0x80 != 0 {
+        temperature = -temperature;
+    }
+    (humidity, temperature)
+}
+
 /// A DHT22 sensor
 pub struct Dht22<
     HE,
@@ -232,7 +440,7 @@ pub struct Dht22<
     D: DelayNs,
     P: InputPin<Error = HE> + OutputPin<Error = HE>,
 > {
-    dht: Dht<HE, ID, D, P>,
+    dht: Dht<HE, ID, D, CountingPulseTiming, P>,
 }
 
 impl<HE, ID: InterruptControl, D: DelayNs, P: InputPin<Error = HE> + OutputPin<Error = HE>>
@@ -240,7 +448,7 @@ impl<HE, ID: InterruptControl, D: DelayNs, P: InputPin<Error = HE> + OutputPin<E
 {
     pub fn new(interrupt_disabler: ID, delay: D, pin: P) -> Self {
         Self {
-            dht: Dht::new(interrupt_disabler, delay, pin),
+            dht: Dht::new(interrupt_disabler, delay, CountingPulseTiming, pin),
         }
     }
 
@@ -261,3 +469,269 @@ impl<HE, ID: InterruptControl, D: DelayNs, P: InputPin<Error = HE> + OutputPin<E
         self.dht.read(Dht22::<HE, ID, D, P>::parse_data)
     }
 }
+
+/// A DHT11 sensor that measures pulse widths against a [`MonotonicClock`] instead of by counting
+/// `delay_us(1)` iterations, for accurate decoding across a wide range of clock speeds
+pub struct Dht11Timed<
+    HE,
+    ID: InterruptControl,
+    D: DelayNs,
+    C: MonotonicClock,
+    P: InputPin<Error = HE> + OutputPin<Error = HE>,
+> {
+    dht: Dht<HE, ID, D, C, P>,
+}
+
+impl<
+        HE,
+        ID: InterruptControl,
+        D: DelayNs,
+        C: MonotonicClock,
+        P: InputPin<Error = HE> + OutputPin<Error = HE>,
+    > Dht11Timed<HE, ID, D, C, P>
+{
+    pub fn new(interrupt_disabler: ID, delay: D, clock: C, pin: P) -> Self {
+        Self {
+            dht: Dht::new(interrupt_disabler, delay, clock, pin),
+        }
+    }
+
+    fn parse_data(buf: &[u8]) -> (f32, f32) {
+        Dht11::<HE, ID, D, P>::parse_data(buf)
+    }
+}
+
+impl<
+        HE,
+        ID: InterruptControl,
+        D: DelayNs,
+        C: MonotonicClock,
+        P: InputPin<Error = HE> + OutputPin<Error = HE>,
+    > DhtSensor<HE> for Dht11Timed<HE, ID, D, C, P>
+{
+    fn read(&mut self) -> Result<Reading, DhtError<HE>> {
+        self.dht.read(Dht11Timed::<HE, ID, D, C, P>::parse_data)
+    }
+}
+
+/// A DHT22 sensor that measures pulse widths against a [`MonotonicClock`] instead of by counting
+/// `delay_us(1)` iterations, for accurate decoding across a wide range of clock speeds
+pub struct Dht22Timed<
+    HE,
+    ID: InterruptControl,
+    D: DelayNs,
+    C: MonotonicClock,
+    P: InputPin<Error = HE> + OutputPin<Error = HE>,
+> {
+    dht: Dht<HE, ID, D, C, P>,
+}
+
+impl<
+        HE,
+        ID: InterruptControl,
+        D: DelayNs,
+        C: MonotonicClock,
+        P: InputPin<Error = HE> + OutputPin<Error = HE>,
+    > Dht22Timed<HE, ID, D, C, P>
+{
+    pub fn new(interrupt_disabler: ID, delay: D, clock: C, pin: P) -> Self {
+        Self {
+            dht: Dht::new(interrupt_disabler, delay, clock, pin),
+        }
+    }
+
+    fn parse_data(buf: &[u8]) -> (f32, f32) {
+        Dht22::<HE, ID, D, P>::parse_data(buf)
+    }
+}
+
+impl<
+        HE,
+        ID: InterruptControl,
+        D: DelayNs,
+        C: MonotonicClock,
+        P: InputPin<Error = HE> + OutputPin<Error = HE>,
+    > DhtSensor<HE> for Dht22Timed<HE, ID, D, C, P>
+{
+    fn read(&mut self) -> Result<Reading, DhtError<HE>> {
+        self.dht.read(Dht22Timed::<HE, ID, D, C, P>::parse_data)
+    }
+}
+
+/// The maximum number of samples [`RetryingDht`] will buffer for averaging
+///
+/// Bounded so `RetryingDht` doesn't need an allocator; `samples` passed to
+/// [`RetryingDht::new`] is clamped to this.
+const MAX_RETRYING_DHT_SAMPLES: usize = 8;
+
+/// How [`RetryingDht::read`] combines multiple successful readings into one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregation {
+    /// Average the humidity and temperature of every successful reading.
+    ///
+    /// The returned [`Reading::raw`] bytes are an arbitrary successful sample's, since no single
+    /// sample corresponds to the averaged values.
+    Mean,
+    /// Return the reading whose humidity is the median of all successful readings.
+    ///
+    /// Unlike `Mean`, the returned reading (including its [`Reading::raw`] bytes) is always one
+    /// of the actual samples taken.
+    Median,
+}
+
+/// A wrapper that retries a flaky [`DhtSensor`] read and can combine several successful readings
+/// into one via an [`Aggregation`]
+pub struct RetryingDht<HE, S: DhtSensor<HE>, D: DelayNs> {
+    sensor: S,
+    delay: D,
+    max_attempts: u8,
+    inter_attempt_delay_us: u32,
+    samples: u8,
+    aggregation: Aggregation,
+    _error: PhantomData<HE>,
+}
+
+impl<HE, S: DhtSensor<HE>, D: DelayNs> RetryingDht<HE, S, D> {
+    /// Creates a new `RetryingDht` wrapping `sensor`.
+    ///
+    /// `max_attempts` is the total number of reads attempted (including the first), waiting
+    /// `inter_attempt_delay_us` microseconds between each. Up to `samples` successful readings
+    /// (capped at [`MAX_RETRYING_DHT_SAMPLES`]) are combined via `aggregation`; use `1` sample to
+    /// simply return the first success.
+    pub fn new(
+        sensor: S,
+        delay: D,
+        max_attempts: u8,
+        inter_attempt_delay_us: u32,
+        samples: u8,
+        aggregation: Aggregation,
+    ) -> Self {
+        Self {
+            sensor,
+            delay,
+            max_attempts: max_attempts.max(1),
+            inter_attempt_delay_us,
+            samples: samples.clamp(1, MAX_RETRYING_DHT_SAMPLES as u8),
+            aggregation,
+            _error: PhantomData,
+        }
+    }
+}
+
+fn retrying_dht_mean(readings: &[Reading]) -> Reading {
+    let count = readings.len() as f32;
+    let humidity = readings.iter().map(Reading::humidity).sum::<f32>() / count;
+    let temperature = readings.iter().map(Reading::temperature).sum::<f32>() / count;
+    Reading::new(humidity, temperature, readings[0].raw())
+}
+
+fn retrying_dht_median(readings: &mut [Reading]) -> Reading {
+    readings.sort_unstable_by(|a, b| a.humidity().partial_cmp(&b.humidity()).unwrap());
+    readings[readings.len() / 2]
+}
+
+impl<HE, S: DhtSensor<HE>, D: DelayNs> DhtSensor<HE> for RetryingDht<HE, S, D> {
+    fn read(&mut self) -> Result<Reading, DhtError<HE>> {
+        let mut readings = [None; MAX_RETRYING_DHT_SAMPLES];
+        let mut successes: usize = 0;
+        let mut last_err = None;
+        let target = self.samples as usize;
+
+        for attempt in 0..self.max_attempts {
+            if attempt > 0 {
+                self.delay.delay_us(self.inter_attempt_delay_us);
+            }
+
+            match self.sensor.read() {
+                Ok(reading) => {
+                    readings[successes] = Some(reading);
+                    successes += 1;
+                    if successes >= target {
+                        break;
+                    }
+                }
+                Err(err) => last_err = Some(err),
+            }
+        }
+
+        if successes == 0 {
+            return Err(last_err.unwrap_or(DhtError::Timeout));
+        }
+
+        let first = readings[0].unwrap();
+        let mut readings: [Reading; MAX_RETRYING_DHT_SAMPLES] =
+            readings.map(|r| r.unwrap_or(first));
+        let readings = &mut readings[..successes];
+        Ok(match self.aggregation {
+            Aggregation::Mean => retrying_dht_mean(readings),
+            Aggregation::Median => retrying_dht_median(readings),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dew_point_matches_reference_values() {
+        let reading = Reading::new(50.0, 25.0, [0; 5]);
+        assert!((reading.dew_point() - 13.842_291).abs() < 0.001);
+
+        let reading = Reading::new(80.0, 30.0, [0; 5]);
+        assert!((reading.dew_point() - 26.160_405).abs() < 0.001);
+    }
+
+    #[test]
+    fn heat_index_uses_simple_formula_below_80f() {
+        // 25 degrees Celsius is 77 degrees Fahrenheit, below the regression's validity threshold
+        let reading = Reading::new(50.0, 25.0, [0; 5]);
+        assert!((reading.heat_index() - 76.75).abs() < 0.001);
+    }
+
+    #[test]
+    fn heat_index_uses_rothfusz_regression_above_80f() {
+        // 35 degrees Celsius is 95 degrees Fahrenheit
+        let reading = Reading::new(70.0, 35.0, [0; 5]);
+        assert!((reading.heat_index() - 122.613_04).abs() < 0.01);
+    }
+
+    #[test]
+    fn dht11_full_resolution_parses_positive_decimal_reading() {
+        let (humidity, temperature) = dht11_full_resolution_parse_data(&[50, 1, 26, 3, 0]);
+        assert!((humidity - 50.1).abs() < 0.001);
+        assert!((temperature - 26.3).abs() < 0.001);
+    }
+
+    #[test]
+    fn dht11_full_resolution_parses_negative_temperature_reading() {
+        let (humidity, temperature) = dht11_full_resolution_parse_data(&[45, 0, 5, 0x82, 0]);
+        assert!((humidity - 45.0).abs() < 0.001);
+        assert!((temperature - (-5.2)).abs() < 0.001);
+    }
+
+    #[test]
+    fn retrying_dht_mean_averages_humidity_and_temperature() {
+        let readings = [
+            Reading::new(40.0, 20.0, [1; 5]),
+            Reading::new(50.0, 24.0, [2; 5]),
+            Reading::new(60.0, 28.0, [3; 5]),
+        ];
+        let mean = retrying_dht_mean(&readings);
+        assert!((mean.humidity() - 50.0).abs() < 0.001);
+        assert!((mean.temperature() - 24.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn retrying_dht_median_returns_an_actual_sample() {
+        let mut readings = [
+            Reading::new(60.0, 28.0, [3; 5]),
+            Reading::new(40.0, 20.0, [1; 5]),
+            Reading::new(50.0, 24.0, [2; 5]),
+        ];
+        let median = retrying_dht_median(&mut readings);
+        assert_eq!(median.raw(), [2; 5]);
+        assert!((median.humidity() - 50.0).abs() < 0.001);
+        assert!((median.temperature() - 24.0).abs() < 0.001);
+    }
+}